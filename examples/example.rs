@@ -10,7 +10,7 @@ use sdl2::render::{Canvas, Texture};
 use sdl2::video::Window;
 use sdl2::EventPump;
 
-use fceux::{MemoryDomain, Snapshot};
+use fceux::{AudioBuffer, MemoryDomain, PixelFormat, Snapshot};
 
 const AUDIO_FREQ: i32 = 44100;
 
@@ -119,6 +119,7 @@ fn cmd_emulate(
     canvas: &mut Canvas<Window>,
     tex: &mut Texture,
     audio: &AudioQueue<i16>,
+    audio_buf: &mut AudioBuffer,
     joy: u8,
 ) -> eyre::Result<()> {
     let mut nmi_called = false;
@@ -135,31 +136,25 @@ fn cmd_emulate(
             joy,
             0,
             |xbuf, soundbuf| {
-                // FCEUX はサウンドバッファが 32bit 単位なので変換が必要。
-                // サンプル単位で処理しているので若干遅そうだが、手元では問題なく鳴っている。
-                // ちゃんとやるなら [i16; 1024] 程度のバッファを用意して変換すべきか。
-                //
-                // なお、AudioQueue::queue() は内部で SDL_QueueAudio() を呼んでいる。
-                // この関数は実装当初は音がおかしかったが、現在は問題ない模様。
-                for sample in soundbuf {
-                    audio.queue(&[*sample as i16]);
-                }
-
-                for y in 0..240 {
-                    for x in 0..256 {
-                        let (r, g, b) = fceux::video_get_palette(xbuf[256 * y + x]);
-                        buf[pitch * y + 4 * x] = 0x00;
-                        buf[pitch * y + 4 * x + 1] = b;
-                        buf[pitch * y + 4 * x + 2] = g;
-                        buf[pitch * y + 4 * x + 3] = r;
-                    }
-                }
+                // FCEUX はサウンドバッファが 32bit 単位なので i16 への変換が要る。
+                // AudioBuffer が変換とクロック同期の両方を面倒見てくれる。
+                // リサンプル比の補正には SDL 側にまだ溜まっている未再生サンプル数
+                // (= audio.size() はバイト単位なので i16 のサイズで割る)を渡す。
+                let host_backlog = (audio.size() / 2) as usize;
+                audio_buf.push_frame(soundbuf, host_backlog);
+
+                fceux::blit_frame(xbuf, buf, pitch, PixelFormat::Rgbx8888);
             },
             &f_hook,
+            None,
+            None,
         );
     })
     .map_err(|s| eyre!(s))?;
 
+    // AudioQueue::queue() は内部で SDL_QueueAudio() を呼んでいる。
+    audio.queue(audio_buf.drain(usize::MAX));
+
     canvas.copy(&tex, None, None).map_err(|s| eyre!(s))?;
     canvas.present();
 
@@ -179,6 +174,8 @@ fn mainloop(
     audio: &AudioQueue<i16>,
 ) -> eyre::Result<()> {
     let snap = fceux::snapshot_create();
+    // 100ms 分のサンプルをバッファに持つことを目標にリサンプル比を調整する。
+    let mut audio_buf = AudioBuffer::new(AUDIO_FREQ as usize / 10);
 
     audio.resume();
     let mut timer = Timer::new(60);
@@ -190,7 +187,7 @@ fn mainloop(
             Cmd::Save => cmd_save(&snap),
             Cmd::Power => cmd_power(),
             Cmd::Reset => cmd_reset(),
-            Cmd::Emulate(joy) => cmd_emulate(canvas, tex, audio, joy)?,
+            Cmd::Emulate(joy) => cmd_emulate(canvas, tex, audio, &mut audio_buf, joy)?,
         }
 
         timer.delay();
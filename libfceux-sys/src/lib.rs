@@ -1,6 +1,11 @@
 use std::os::raw::{c_char, c_int, c_uint, c_void};
 
 pub const FCEUX_MEMORY_CPU: FceuxMemoryDomain = 0;
+pub const FCEUX_MEMORY_PPU: FceuxMemoryDomain = 1;
+pub const FCEUX_MEMORY_OAM: FceuxMemoryDomain = 2;
+pub const FCEUX_MEMORY_PALETTE: FceuxMemoryDomain = 3;
+pub const FCEUX_MEMORY_NAMETABLE: FceuxMemoryDomain = 4;
+pub const FCEUX_MEMORY_SRAM: FceuxMemoryDomain = 5;
 pub type FceuxMemoryDomain = c_uint;
 
 #[repr(C)]
@@ -10,6 +15,10 @@ pub struct Snapshot {
 }
 
 pub type FceuxHookBeforeExec = Option<unsafe extern "C" fn(userdata: *mut c_void, addr: u16)>;
+pub type FceuxHookMemRead =
+    Option<unsafe extern "C" fn(userdata: *mut c_void, addr: u16, value: u8)>;
+pub type FceuxHookMemWrite =
+    Option<unsafe extern "C" fn(userdata: *mut c_void, addr: u16, value: u8)>;
 
 extern "C" {
     pub fn fceux_init(path_rom: *const c_char) -> c_int;
@@ -26,12 +35,31 @@ extern "C" {
     pub fn fceux_mem_read(addr: u16, domain: FceuxMemoryDomain) -> u8;
     pub fn fceux_mem_write(addr: u16, value: u8, domain: FceuxMemoryDomain);
 
+    pub fn fceux_reg_p() -> u8;
+    pub fn fceux_reg_a() -> u8;
+    pub fn fceux_reg_x() -> u8;
+    pub fn fceux_reg_y() -> u8;
+    pub fn fceux_reg_s() -> u8;
+    pub fn fceux_reg_pc() -> u16;
+
     pub fn fceux_snapshot_create() -> *mut Snapshot;
     pub fn fceux_snapshot_destroy(snap: *mut Snapshot);
     pub fn fceux_snapshot_load(snap: *mut Snapshot) -> c_int;
     pub fn fceux_snapshot_save(snap: *mut Snapshot) -> c_int;
 
+    /// `snap` の内容をシリアライズし、`*data`/`*size` にバッファを設定する。
+    /// 返されたバッファは `fceux_snapshot_free_buffer()` で解放すること。
+    pub fn fceux_snapshot_serialize(
+        snap: *mut Snapshot,
+        data: *mut *mut u8,
+        size: *mut c_int,
+    ) -> c_int;
+    pub fn fceux_snapshot_deserialize(snap: *mut Snapshot, data: *const u8, size: c_int) -> c_int;
+    pub fn fceux_snapshot_free_buffer(data: *mut u8);
+
     pub fn fceux_hook_before_exec(hook: FceuxHookBeforeExec, userdata: *mut c_void);
+    pub fn fceux_hook_mem_read(hook: FceuxHookMemRead, userdata: *mut c_void);
+    pub fn fceux_hook_mem_write(hook: FceuxHookMemWrite, userdata: *mut c_void);
 
     pub fn fceux_video_get_palette(idx: u8, r: *mut u8, g: *mut u8, b: *mut u8);
 
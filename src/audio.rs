@@ -0,0 +1,129 @@
+//! サウンド出力用バッファ。
+//!
+//! `run_frame()` が返す `&[i32]` のサウンドバッファを `i16` へ一括変換し、
+//! ホスト側の再生キュー長を目標レイテンシに近づけるようリサンプル比を
+//! 少しずつ補正する。エミュレータ側とホスト側のクロックのずれによる
+//! アンダーラン/オーバーランを防ぐのが狙い。
+
+use std::collections::VecDeque;
+
+/// 1 フレームごとにリサンプル比を補正する量。
+const RATE_ADJUST_STEP: f64 = 0.005;
+const RATE_MIN: f64 = 1.0 - RATE_ADJUST_STEP;
+const RATE_MAX: f64 = 1.0 + RATE_ADJUST_STEP;
+
+/// `i32` サウンドバッファを `i16` に変換しつつキューイングするバッファ。
+///
+/// `push_frame()` で 1 フレーム分のサンプルを積み、`drain()` で再生側に渡す分を
+/// 取り出す。
+///
+/// リサンプル比の補正は `push_frame()` に渡す `host_backlog`(ホスト側の再生
+/// キューにまだ残っているサンプル数)を `target_latency` と比較して行う。
+/// `AudioBuffer` 自身の内部キューは `drain()` で好きなだけ空にできてしまうため、
+/// 自身のキュー長では本当のレイテンシを測れない。ホスト側の実際の残量を
+/// 呼び出し側から報告してもらうことで、溜まりすぎなら速め、不足気味なら
+/// 遅めにリサンプルし、ホスト側キュー長を目標値付近に保つ。
+#[derive(Debug)]
+pub struct AudioBuffer {
+    queue: VecDeque<i16>,
+    drain_buf: Vec<i16>,
+    target_latency: usize,
+    ratio: f64,
+    pos: f64,
+}
+
+impl AudioBuffer {
+    /// `target_latency`: 維持したいホスト側再生キューの残量(サンプル数)。
+    pub fn new(target_latency: usize) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            drain_buf: Vec::new(),
+            target_latency,
+            ratio: 1.0,
+            pos: 0.0,
+        }
+    }
+
+    /// 現在の実効リサンプル比。1.0 より大きいほど再生を速め、小さいほど遅める。
+    pub fn ratio(&self) -> f64 {
+        self.ratio
+    }
+
+    /// 内部キューに溜まっている(まだ `drain()` されていない)サンプル数。
+    pub fn queued_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// 1 フレーム分のサウンドバッファを `i16` に変換してキューへ積む。
+    ///
+    /// `host_backlog` には、呼び出し側の再生バックエンド(SDL `AudioQueue` など)
+    /// にまだ溜まっている未再生サンプル数を渡すこと。これが `target_latency` を
+    /// 上回っていれば再生を速め、下回っていれば遅めるようリサンプル比を補正する。
+    pub fn push_frame(&mut self, samples: &[i32], host_backlog: usize) {
+        if samples.is_empty() {
+            return;
+        }
+
+        if host_backlog > self.target_latency {
+            self.ratio = (self.ratio + RATE_ADJUST_STEP).min(RATE_MAX);
+        } else if host_backlog < self.target_latency {
+            self.ratio = (self.ratio - RATE_ADJUST_STEP).max(RATE_MIN);
+        } else {
+            self.ratio = 1.0;
+        }
+
+        let len = samples.len() as f64;
+        while self.pos < len {
+            let idx = (self.pos as usize).min(samples.len() - 1);
+            self.queue.push_back(clamp_i16(samples[idx]));
+            self.pos += self.ratio;
+        }
+        self.pos -= len;
+
+        debug_assert!(self.pos >= 0.0);
+    }
+
+    /// キューから最大 `max` 個のサンプルを取り出す。
+    pub fn drain(&mut self, max: usize) -> &[i16] {
+        let n = max.min(self.queue.len());
+
+        self.drain_buf.clear();
+        self.drain_buf.extend(self.queue.drain(..n));
+
+        &self.drain_buf
+    }
+}
+
+fn clamp_i16(sample: i32) -> i16 {
+    sample.clamp(i16::MIN as i32, i16::MAX as i32) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ratio_speeds_up_when_host_backlog_exceeds_target() {
+        let mut buf = AudioBuffer::new(100);
+        for _ in 0..5 {
+            buf.push_frame(&[0; 64], 1000);
+        }
+        assert_eq!(buf.ratio(), RATE_MAX);
+    }
+
+    #[test]
+    fn ratio_slows_down_when_host_backlog_is_below_target() {
+        let mut buf = AudioBuffer::new(1000);
+        for _ in 0..5 {
+            buf.push_frame(&[0; 64], 10);
+        }
+        assert_eq!(buf.ratio(), RATE_MIN);
+    }
+
+    #[test]
+    fn ratio_settles_at_one_when_host_backlog_matches_target() {
+        let mut buf = AudioBuffer::new(100);
+        buf.push_frame(&[0; 64], 100);
+        assert_eq!(buf.ratio(), 1.0);
+    }
+}
@@ -0,0 +1,174 @@
+//! 6502 逆アセンブラ。
+//!
+//! 正規 151 命令に加え、よく知られた非公式命令(illegal opcode)もデコードする。
+
+use crate::{mem_read, MemoryDomain};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum AddrMode {
+    Implied,
+    Accumulator,
+    Immediate,
+    ZeroPage,
+    ZeroPageX,
+    ZeroPageY,
+    Absolute,
+    AbsoluteX,
+    AbsoluteY,
+    Indirect,
+    IndirectX,
+    IndirectY,
+    Relative,
+}
+
+impl AddrMode {
+    /// オペコード自身を含まない、オペランドのバイト数を返す。
+    fn operand_len(self) -> u8 {
+        match self {
+            Self::Implied | Self::Accumulator => 0,
+            Self::Immediate
+            | Self::ZeroPage
+            | Self::ZeroPageX
+            | Self::ZeroPageY
+            | Self::IndirectX
+            | Self::IndirectY
+            | Self::Relative => 1,
+            Self::Absolute | Self::AbsoluteX | Self::AbsoluteY | Self::Indirect => 2,
+        }
+    }
+}
+
+#[rustfmt::skip]
+const OPCODES: [(&str, AddrMode); 256] = {
+    use AddrMode::*;
+    [
+        ("BRK", Implied),    ("ORA", IndirectX),  ("KIL", Implied),    ("SLO", IndirectX),
+        ("NOP", ZeroPage),   ("ORA", ZeroPage),   ("ASL", ZeroPage),   ("SLO", ZeroPage),
+        ("PHP", Implied),    ("ORA", Immediate),  ("ASL", Accumulator),("ANC", Immediate),
+        ("NOP", Absolute),   ("ORA", Absolute),   ("ASL", Absolute),   ("SLO", Absolute),
+
+        ("BPL", Relative),   ("ORA", IndirectY),  ("KIL", Implied),    ("SLO", IndirectY),
+        ("NOP", ZeroPageX),  ("ORA", ZeroPageX),  ("ASL", ZeroPageX),  ("SLO", ZeroPageX),
+        ("CLC", Implied),    ("ORA", AbsoluteY),  ("NOP", Implied),    ("SLO", AbsoluteY),
+        ("NOP", AbsoluteX),  ("ORA", AbsoluteX),  ("ASL", AbsoluteX),  ("SLO", AbsoluteX),
+
+        ("JSR", Absolute),   ("AND", IndirectX),  ("KIL", Implied),    ("RLA", IndirectX),
+        ("BIT", ZeroPage),   ("AND", ZeroPage),   ("ROL", ZeroPage),   ("RLA", ZeroPage),
+        ("PLP", Implied),    ("AND", Immediate),  ("ROL", Accumulator),("ANC", Immediate),
+        ("BIT", Absolute),   ("AND", Absolute),   ("ROL", Absolute),   ("RLA", Absolute),
+
+        ("BMI", Relative),   ("AND", IndirectY),  ("KIL", Implied),    ("RLA", IndirectY),
+        ("NOP", ZeroPageX),  ("AND", ZeroPageX),  ("ROL", ZeroPageX),  ("RLA", ZeroPageX),
+        ("SEC", Implied),    ("AND", AbsoluteY),  ("NOP", Implied),    ("RLA", AbsoluteY),
+        ("NOP", AbsoluteX),  ("AND", AbsoluteX),  ("ROL", AbsoluteX),  ("RLA", AbsoluteX),
+
+        ("RTI", Implied),    ("EOR", IndirectX),  ("KIL", Implied),    ("SRE", IndirectX),
+        ("NOP", ZeroPage),   ("EOR", ZeroPage),   ("LSR", ZeroPage),   ("SRE", ZeroPage),
+        ("PHA", Implied),    ("EOR", Immediate),  ("LSR", Accumulator),("ALR", Immediate),
+        ("JMP", Absolute),   ("EOR", Absolute),   ("LSR", Absolute),   ("SRE", Absolute),
+
+        ("BVC", Relative),   ("EOR", IndirectY),  ("KIL", Implied),    ("SRE", IndirectY),
+        ("NOP", ZeroPageX),  ("EOR", ZeroPageX),  ("LSR", ZeroPageX),  ("SRE", ZeroPageX),
+        ("CLI", Implied),    ("EOR", AbsoluteY),  ("NOP", Implied),    ("SRE", AbsoluteY),
+        ("NOP", AbsoluteX),  ("EOR", AbsoluteX),  ("LSR", AbsoluteX),  ("SRE", AbsoluteX),
+
+        ("RTS", Implied),    ("ADC", IndirectX),  ("KIL", Implied),    ("RRA", IndirectX),
+        ("NOP", ZeroPage),   ("ADC", ZeroPage),   ("ROR", ZeroPage),   ("RRA", ZeroPage),
+        ("PLA", Implied),    ("ADC", Immediate),  ("ROR", Accumulator),("ARR", Immediate),
+        ("JMP", Indirect),   ("ADC", Absolute),   ("ROR", Absolute),   ("RRA", Absolute),
+
+        ("BVS", Relative),   ("ADC", IndirectY),  ("KIL", Implied),    ("RRA", IndirectY),
+        ("NOP", ZeroPageX),  ("ADC", ZeroPageX),  ("ROR", ZeroPageX),  ("RRA", ZeroPageX),
+        ("SEI", Implied),    ("ADC", AbsoluteY),  ("NOP", Implied),    ("RRA", AbsoluteY),
+        ("NOP", AbsoluteX),  ("ADC", AbsoluteX),  ("ROR", AbsoluteX),  ("RRA", AbsoluteX),
+
+        ("NOP", Immediate),  ("STA", IndirectX),  ("NOP", Immediate),  ("SAX", IndirectX),
+        ("STY", ZeroPage),   ("STA", ZeroPage),   ("STX", ZeroPage),   ("SAX", ZeroPage),
+        ("DEY", Implied),    ("NOP", Immediate),  ("TXA", Implied),    ("XAA", Immediate),
+        ("STY", Absolute),   ("STA", Absolute),   ("STX", Absolute),   ("SAX", Absolute),
+
+        ("BCC", Relative),   ("STA", IndirectY),  ("KIL", Implied),    ("SHA", IndirectY),
+        ("STY", ZeroPageX),  ("STA", ZeroPageX),  ("STX", ZeroPageY),  ("SAX", ZeroPageY),
+        ("TYA", Implied),    ("STA", AbsoluteY),  ("TXS", Implied),    ("TAS", AbsoluteY),
+        ("SHY", AbsoluteX),  ("STA", AbsoluteX),  ("SHX", AbsoluteY),  ("SHA", AbsoluteY),
+
+        ("LDY", Immediate),  ("LDA", IndirectX),  ("LDX", Immediate),  ("LAX", IndirectX),
+        ("LDY", ZeroPage),   ("LDA", ZeroPage),   ("LDX", ZeroPage),   ("LAX", ZeroPage),
+        ("TAY", Implied),    ("LDA", Immediate),  ("TAX", Implied),    ("LAX", Immediate),
+        ("LDY", Absolute),   ("LDA", Absolute),   ("LDX", Absolute),   ("LAX", Absolute),
+
+        ("BCS", Relative),   ("LDA", IndirectY),  ("KIL", Implied),    ("LAX", IndirectY),
+        ("LDY", ZeroPageX),  ("LDA", ZeroPageX),  ("LDX", ZeroPageY),  ("LAX", ZeroPageY),
+        ("CLV", Implied),    ("LDA", AbsoluteY),  ("TSX", Implied),    ("LAS", AbsoluteY),
+        ("LDY", AbsoluteX),  ("LDA", AbsoluteX),  ("LDX", AbsoluteY),  ("LAX", AbsoluteY),
+
+        ("CPY", Immediate),  ("CMP", IndirectX),  ("NOP", Immediate),  ("DCP", IndirectX),
+        ("CPY", ZeroPage),   ("CMP", ZeroPage),   ("DEC", ZeroPage),   ("DCP", ZeroPage),
+        ("INY", Implied),    ("CMP", Immediate),  ("DEX", Implied),    ("SBX", Immediate),
+        ("CPY", Absolute),   ("CMP", Absolute),   ("DEC", Absolute),   ("DCP", Absolute),
+
+        ("BNE", Relative),   ("CMP", IndirectY),  ("KIL", Implied),    ("DCP", IndirectY),
+        ("NOP", ZeroPageX),  ("CMP", ZeroPageX),  ("DEC", ZeroPageX),  ("DCP", ZeroPageX),
+        ("CLD", Implied),    ("CMP", AbsoluteY),  ("NOP", Implied),    ("DCP", AbsoluteY),
+        ("NOP", AbsoluteX),  ("CMP", AbsoluteX),  ("DEC", AbsoluteX),  ("DCP", AbsoluteX),
+
+        ("CPX", Immediate),  ("SBC", IndirectX),  ("NOP", Immediate),  ("ISC", IndirectX),
+        ("CPX", ZeroPage),   ("SBC", ZeroPage),   ("INC", ZeroPage),   ("ISC", ZeroPage),
+        ("INX", Implied),    ("SBC", Immediate),  ("NOP", Implied),    ("SBC", Immediate),
+        ("CPX", Absolute),   ("SBC", Absolute),   ("INC", Absolute),   ("ISC", Absolute),
+
+        ("BEQ", Relative),   ("SBC", IndirectY),  ("KIL", Implied),    ("ISC", IndirectY),
+        ("NOP", ZeroPageX),  ("SBC", ZeroPageX),  ("INC", ZeroPageX),  ("ISC", ZeroPageX),
+        ("SED", Implied),    ("SBC", AbsoluteY),  ("NOP", Implied),    ("ISC", AbsoluteY),
+        ("NOP", AbsoluteX),  ("SBC", AbsoluteX),  ("INC", AbsoluteX),  ("ISC", AbsoluteX),
+    ]
+};
+
+/// `addr` にあるオペコードを逆アセンブルし、ニーモニック文字列と命令長(バイト数)を返す。
+///
+/// オペランドは `domain` で指定したメモリドメインから読む。
+pub fn disassemble(addr: u16, domain: MemoryDomain) -> (String, u8) {
+    let opcode = mem_read(addr, domain);
+    let (mnemonic, mode) = OPCODES[opcode as usize];
+    let len = 1 + mode.operand_len();
+
+    let operand = |off: u16| mem_read(addr.wrapping_add(off), domain);
+
+    let text = match mode {
+        AddrMode::Implied => mnemonic.to_string(),
+        AddrMode::Accumulator => format!("{} A", mnemonic),
+        AddrMode::Immediate => format!("{} #${:02X}", mnemonic, operand(1)),
+        AddrMode::ZeroPage => format!("{} ${:02X}", mnemonic, operand(1)),
+        AddrMode::ZeroPageX => format!("{} ${:02X},X", mnemonic, operand(1)),
+        AddrMode::ZeroPageY => format!("{} ${:02X},Y", mnemonic, operand(1)),
+        AddrMode::IndirectX => format!("{} (${:02X},X)", mnemonic, operand(1)),
+        AddrMode::IndirectY => format!("{} (${:02X}),Y", mnemonic, operand(1)),
+        AddrMode::Absolute => {
+            let lo = operand(1) as u16;
+            let hi = operand(2) as u16;
+            format!("{} ${:04X}", mnemonic, lo | (hi << 8))
+        }
+        AddrMode::AbsoluteX => {
+            let lo = operand(1) as u16;
+            let hi = operand(2) as u16;
+            format!("{} ${:04X},X", mnemonic, lo | (hi << 8))
+        }
+        AddrMode::AbsoluteY => {
+            let lo = operand(1) as u16;
+            let hi = operand(2) as u16;
+            format!("{} ${:04X},Y", mnemonic, lo | (hi << 8))
+        }
+        AddrMode::Indirect => {
+            let lo = operand(1) as u16;
+            let hi = operand(2) as u16;
+            format!("{} (${:04X})", mnemonic, lo | (hi << 8))
+        }
+        AddrMode::Relative => {
+            let offset = operand(1) as i8;
+            let target = addr.wrapping_add(2).wrapping_add(offset as u16);
+            format!("{} ${:04X}", mnemonic, target)
+        }
+    };
+
+    (text, len)
+}
@@ -6,6 +6,12 @@ use std::ffi::CString;
 use std::os::raw::{c_int, c_uint, c_void};
 use std::path::Path;
 
+mod audio;
+mod disasm;
+
+pub use audio::AudioBuffer;
+pub use disasm::disassemble;
+
 #[derive(Debug, thiserror::Error)]
 #[error("fceux error: {0}")]
 pub struct Error(String);
@@ -45,6 +51,7 @@ impl RegP {
 }
 
 fn hook_dummy(_addr: u16) {}
+fn hook_mem_dummy(_addr: u16, _value: u8) {}
 
 struct Hook {
     f: UnsafeCell<*const dyn FnMut(u16)>,
@@ -68,15 +75,52 @@ impl Hook {
 
 unsafe impl Sync for Hook {}
 
+/// メモリ read/write フック用。exec フックとシグネチャが異なるため別型にしている。
+struct HookMem {
+    f: UnsafeCell<*const dyn FnMut(u16, u8)>,
+}
+
+impl HookMem {
+    fn replace(&self, f: &dyn FnMut(u16, u8)) {
+        unsafe {
+            let f: &'static dyn FnMut(u16, u8) = std::mem::transmute(f);
+            *self.f.get() = f;
+        }
+    }
+
+    fn call(&self, addr: u16, value: u8) {
+        unsafe {
+            let f: &mut dyn FnMut(u16, u8) = &mut *(*self.f.get() as *mut dyn FnMut(u16, u8));
+            f(addr, value);
+        }
+    }
+}
+
+unsafe impl Sync for HookMem {}
+
 static mut INITIALIZED: bool = false;
 static HOOK: Hook = Hook {
     f: UnsafeCell::new(&hook_dummy),
 };
+static HOOK_READ: HookMem = HookMem {
+    f: UnsafeCell::new(&hook_mem_dummy),
+};
+static HOOK_WRITE: HookMem = HookMem {
+    f: UnsafeCell::new(&hook_mem_dummy),
+};
 
 unsafe extern "C" fn ffi_hook_before_exec(_: *mut c_void, addr: u16) {
     HOOK.call(addr);
 }
 
+unsafe extern "C" fn ffi_hook_mem_read(_: *mut c_void, addr: u16, value: u8) {
+    HOOK_READ.call(addr, value);
+}
+
+unsafe extern "C" fn ffi_hook_mem_write(_: *mut c_void, addr: u16, value: u8) {
+    HOOK_WRITE.call(addr, value);
+}
+
 /// 初期化処理。
 /// この関数が成功する前に他の関数を使った場合の結果は未定義。
 ///
@@ -104,6 +148,8 @@ pub fn init(path_rom: impl AsRef<Path>) -> Result<()> {
         }
 
         libfceux_sys::fceux_hook_before_exec(Some(ffi_hook_before_exec), std::ptr::null_mut());
+        libfceux_sys::fceux_hook_mem_read(Some(ffi_hook_mem_read), std::ptr::null_mut());
+        libfceux_sys::fceux_hook_mem_write(Some(ffi_hook_mem_write), std::ptr::null_mut());
 
         INITIALIZED = true;
     }
@@ -128,15 +174,26 @@ pub fn reset() {
 }
 
 /// フレーム境界以外から呼び出した場合の結果は未定義。
+///
+/// `f_hook_read`/`f_hook_write` を指定すると、そのフレーム中の全メモリ read/write
+/// アクセスごとに呼ばれる。read/write ブレークポイントやウォッチポイントの実装に使う。
 pub fn run_frame<VideoSoundF>(
     joy1: u8,
     joy2: u8,
     f_video_sound: VideoSoundF,
     f_hook: &dyn FnMut(u16),
+    f_hook_read: Option<&dyn FnMut(u16, u8)>,
+    f_hook_write: Option<&dyn FnMut(u16, u8)>,
 ) where
     VideoSoundF: FnOnce(&[u8], &[i32]),
 {
     HOOK.replace(f_hook);
+    if let Some(f) = f_hook_read {
+        HOOK_READ.replace(f);
+    }
+    if let Some(f) = f_hook_write {
+        HOOK_WRITE.replace(f);
+    }
 
     let mut xbuf: *mut u8 = std::ptr::null_mut();
     let mut soundbuf: *mut i32 = std::ptr::null_mut();
@@ -151,6 +208,8 @@ pub fn run_frame<VideoSoundF>(
     f_video_sound(xbuf, soundbuf);
 
     HOOK.replace(&hook_dummy);
+    HOOK_READ.replace(&hook_mem_dummy);
+    HOOK_WRITE.replace(&hook_mem_dummy);
 }
 
 /// P レジスタを読み取る。
@@ -159,6 +218,31 @@ pub fn reg_p() -> RegP {
     RegP(inner)
 }
 
+/// A レジスタを読み取る。
+pub fn reg_a() -> u8 {
+    unsafe { libfceux_sys::fceux_reg_a() }
+}
+
+/// X レジスタを読み取る。
+pub fn reg_x() -> u8 {
+    unsafe { libfceux_sys::fceux_reg_x() }
+}
+
+/// Y レジスタを読み取る。
+pub fn reg_y() -> u8 {
+    unsafe { libfceux_sys::fceux_reg_y() }
+}
+
+/// S レジスタ(スタックポインタ)を読み取る。
+pub fn reg_s() -> u8 {
+    unsafe { libfceux_sys::fceux_reg_s() }
+}
+
+/// PC レジスタ(プログラムカウンタ)を読み取る。
+pub fn reg_pc() -> u16 {
+    unsafe { libfceux_sys::fceux_reg_pc() }
+}
+
 pub fn mem_read(addr: u16, domain: MemoryDomain) -> u8 {
     unsafe { libfceux_sys::fceux_mem_read(addr, domain as c_uint) }
 }
@@ -189,6 +273,45 @@ pub fn snapshot_save(snap: &Snapshot) -> Result<()> {
     Ok(())
 }
 
+/// セーブステートをバイト列へシリアライズする。
+///
+/// ファイルへの保存やリワインドバッファへの蓄積など、`Snapshot` をプロセス外へ
+/// 持ち出したい場合に使う。
+pub fn snapshot_to_bytes(snap: &Snapshot) -> Result<Vec<u8>> {
+    let mut data: *mut u8 = std::ptr::null_mut();
+    let mut size: c_int = 0;
+
+    unsafe {
+        let status = libfceux_sys::fceux_snapshot_serialize(snap.snap, &mut data, &mut size);
+        if status == 0 {
+            return Err(Error::new("fceux_snapshot_serialize() failed"));
+        }
+
+        let bytes = if data.is_null() {
+            Vec::new()
+        } else {
+            std::slice::from_raw_parts(data, size as usize).to_vec()
+        };
+        libfceux_sys::fceux_snapshot_free_buffer(data);
+        Ok(bytes)
+    }
+}
+
+/// `snapshot_to_bytes()` で得たバイト列から `Snapshot` を復元する。
+pub fn snapshot_from_bytes(data: &[u8]) -> Result<Snapshot> {
+    let size = c_int::try_from(data.len()).map_err(|_| Error::new("snapshot data too large"))?;
+
+    let snap = Snapshot::new();
+
+    let status =
+        unsafe { libfceux_sys::fceux_snapshot_deserialize(snap.snap, data.as_ptr(), size) };
+    if status == 0 {
+        return Err(Error::new("fceux_snapshot_deserialize() failed"));
+    }
+
+    Ok(snap)
+}
+
 pub fn video_get_palette(idx: u8) -> (u8, u8, u8) {
     let mut r = 0;
     let mut g = 0;
@@ -199,6 +322,63 @@ pub fn video_get_palette(idx: u8) -> (u8, u8, u8) {
     (r, g, b)
 }
 
+/// 現在アクティブなパレット(64 色)を一括取得する。
+///
+/// ピクセルごとに `video_get_palette()` を呼ぶと 256x240 フレームあたり 61440 回の
+/// FFI 呼び出しが発生するため、`blit_frame()` ではこちらを使ってパレットを一度だけ
+/// 取得してから変換する。
+pub fn video_palette_all() -> [(u8, u8, u8); 64] {
+    let mut table = [(0u8, 0u8, 0u8); 64];
+    for (idx, entry) in table.iter_mut().enumerate() {
+        *entry = video_get_palette(idx as u8);
+    }
+    table
+}
+
+/// `blit_frame()` の出力バイト順。
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PixelFormat {
+    /// メモリ上のバイト順は `[X, B, G, R]`(SDL の `RGBX8888` 相当)。
+    Rgbx8888,
+    /// メモリ上のバイト順は `[X, R, G, B]`(SDL の `BGRX8888` 相当)。
+    Bgrx8888,
+}
+
+/// `run_frame()` が返す 256x240 のインデックスバッファ `xbuf` を、パレットを引いて
+/// `format` で指定したバイト順の packed RGB バッファ `out` に展開する。
+///
+/// `pitch` は `out` の 1 行あたりのバイト数。ロックしたテクスチャの pitch は
+/// アライメントの都合で `256 * 4` より大きくなることがあるため、呼び出し側が
+/// 実際の pitch を渡すこと。`out` は最低 `pitch * 240` バイト必要。
+pub fn blit_frame(xbuf: &[u8], out: &mut [u8], pitch: usize, format: PixelFormat) {
+    assert_eq!(xbuf.len(), 256 * 240);
+    assert!(pitch >= 256 * 4, "pitch too small for 256 RGBX8888 pixels");
+    assert!(out.len() >= pitch * 240, "out buffer too small for pitch * 240 rows");
+
+    let palette = video_palette_all();
+
+    for y in 0..240 {
+        for x in 0..256 {
+            let (r, g, b) = palette[xbuf[256 * y + x] as usize];
+            let px = &mut out[pitch * y + 4 * x..pitch * y + 4 * x + 4];
+            match format {
+                PixelFormat::Rgbx8888 => {
+                    px[0] = 0x00;
+                    px[1] = b;
+                    px[2] = g;
+                    px[3] = r;
+                }
+                PixelFormat::Bgrx8888 => {
+                    px[0] = 0x00;
+                    px[1] = r;
+                    px[2] = g;
+                    px[3] = b;
+                }
+            }
+        }
+    }
+}
+
 pub fn sound_set_freq(freq: i32) -> Result<()> {
     let status = unsafe { libfceux_sys::fceux_sound_set_freq(freq as c_int) };
     if status == 0 {
@@ -211,6 +391,11 @@ pub fn sound_set_freq(freq: i32) -> Result<()> {
 #[repr(u32)]
 pub enum MemoryDomain {
     Cpu = libfceux_sys::FCEUX_MEMORY_CPU,
+    Ppu = libfceux_sys::FCEUX_MEMORY_PPU,
+    Oam = libfceux_sys::FCEUX_MEMORY_OAM,
+    PaletteRam = libfceux_sys::FCEUX_MEMORY_PALETTE,
+    Nametable = libfceux_sys::FCEUX_MEMORY_NAMETABLE,
+    Sram = libfceux_sys::FCEUX_MEMORY_SRAM,
 }
 
 #[derive(Debug)]